@@ -0,0 +1,144 @@
+// Copyright 2019 Developers of the Rand project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::fmt;
+use core::num::NonZeroU32;
+
+/// A small and `no_std` compatible error type.
+///
+/// The [`Error::raw_os_error()`] will indicate if the error is from the OS, and
+/// if so, which error code the OS gave the application. If such an error is
+/// encountered, please consult with your system documentation.
+///
+/// *If this crate's `"std"` feature is enabled*, then:
+/// - [`getrandom::Error`][`Error`] implements
+///   [`std::error::Error`](https://doc.rust-lang.org/std/error/trait.Error.html)
+/// - [`std::io::Error`](https://doc.rust-lang.org/std/io/struct.Error.html) implements
+///   [`From<getrandom::Error>`](https://doc.rust-lang.org/std/convert/trait.From.html).
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct Error(NonZeroU32);
+
+const fn internal_error(n: u16) -> Error {
+    // SAFETY: code > 0 since INTERNAL_START > 0 and adding n won't overflow.
+    let code = Error::INTERNAL_START + (n as u32);
+    Error(unsafe { NonZeroU32::new_unchecked(code) })
+}
+
+/// The platform-specific `errno` returned a non-positive value.
+pub(crate) const ERRNO_NOT_POSITIVE: Error = internal_error(0);
+/// Call to iOS [`SecRandomCopyBytes`](https://developer.apple.com/documentation/security/1399291-secrandomcopybytes) failed.
+pub(crate) const IOS_SEC_RANDOM: Error = internal_error(3);
+/// Call to Windows [`RtlGenRandom`](https://docs.microsoft.com/en-us/windows/win32/api/ntsecapi/nf-ntsecapi-rtlgenrandom) failed.
+pub(crate) const WINDOWS_RTL_GEN_RANDOM: Error = internal_error(4);
+/// RDRAND instruction failed to provide entropy after several attempts.
+pub(crate) const FAILED_RDRAND: Error = internal_error(5);
+/// The RDRAND instruction is not supported by this CPU.
+pub(crate) const NO_RDRAND: Error = internal_error(6);
+/// The browser does not have support for `self.crypto`.
+pub(crate) const BINDGEN_CRYPTO_UNDEF: Error = internal_error(7);
+/// The browser does not have support for `crypto.getRandomValues`.
+pub(crate) const BINDGEN_GRV_UNDEF: Error = internal_error(8);
+/// No usable entropy source was found on the JS global object, so the Node
+/// `crypto` module was tried but `module`/`require` or the module itself was
+/// unavailable.
+///
+/// Note that a browser whose global defines neither `crypto` nor `msCrypto`
+/// also falls through to the Node path and reports this code; it does not
+/// necessarily indicate an actual Node runtime, only the absence of a Web
+/// Crypto object.
+pub(crate) const BINDGEN_NODE_CRYPTO_UNDEF: Error = internal_error(9);
+/// The user-registered custom JS entropy source threw while filling the buffer.
+pub(crate) const CUSTOM_RNG_FAILED: Error = internal_error(11);
+
+impl Error {
+    /// This target/platform is not supported by `getrandom`.
+    pub const UNSUPPORTED: Error = internal_error(10);
+    /// The platform-specific `errno` returned a non-positive value.
+    pub const ERRNO_NOT_POSITIVE: Error = ERRNO_NOT_POSITIVE;
+
+    /// Codes below this point represent OS Errors (i.e. positive i32 values).
+    /// Codes at or above this point, but below [`Error::CUSTOM_START`] are
+    /// reserved for use by the `rand` and `getrandom` crates.
+    pub const INTERNAL_START: u32 = 1 << 31;
+
+    /// Codes at or above this point can be used by users to define their own
+    /// custom errors.
+    pub const CUSTOM_START: u32 = (1 << 31) + (1 << 30);
+
+    /// Extract the raw OS error code (if this error came from the OS)
+    ///
+    /// This method is identical to `std::io::Error::raw_os_error()`, except
+    /// that it works in `no_std` contexts. If this method returns `None`, the
+    /// error value can still be formatted via the `Display` implementation.
+    #[inline]
+    pub fn raw_os_error(self) -> Option<i32> {
+        if self.0.get() < Self::INTERNAL_START {
+            Some(self.0.get() as i32)
+        } else {
+            None
+        }
+    }
+
+    /// Extract the bare error code.
+    ///
+    /// This code can either come from the underlying OS, or be a custom error.
+    /// Use [`Error::raw_os_error()`] to disambiguate.
+    #[inline]
+    pub fn code(self) -> NonZeroU32 {
+        self.0
+    }
+
+    fn internal_desc(&self) -> Option<&'static str> {
+        match *self {
+            ERRNO_NOT_POSITIVE => Some("errno: did not return a positive value"),
+            IOS_SEC_RANDOM => Some("SecRandomCopyBytes: iOS Security framework failure"),
+            WINDOWS_RTL_GEN_RANDOM => Some("RtlGenRandom: Windows system function failure"),
+            FAILED_RDRAND => Some("RDRAND: failed multiple times: CPU issue likely"),
+            NO_RDRAND => Some("RDRAND: instruction not supported"),
+            BINDGEN_CRYPTO_UNDEF => Some("wasm-bindgen: self.crypto is undefined"),
+            BINDGEN_GRV_UNDEF => Some("wasm-bindgen: crypto.getRandomValues is undefined"),
+            BINDGEN_NODE_CRYPTO_UNDEF => Some("wasm-bindgen: Node crypto module is unavailable"),
+            CUSTOM_RNG_FAILED => Some("wasm-bindgen: custom RNG source failed"),
+            Error::UNSUPPORTED => Some("getrandom: this target is not supported"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dbg = f.debug_struct("Error");
+        if let Some(errno) = self.raw_os_error() {
+            dbg.field("os_error", &errno);
+        } else if let Some(desc) = self.internal_desc() {
+            dbg.field("internal_code", &self.0.get());
+            dbg.field("description", &desc);
+        } else {
+            dbg.field("unknown_code", &self.0.get());
+        }
+        dbg.finish()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(errno) = self.raw_os_error() {
+            write!(f, "OS Error: {}", errno)
+        } else if let Some(desc) = self.internal_desc() {
+            f.write_str(desc)
+        } else {
+            write!(f, "Unknown Error: {}", self.0.get())
+        }
+    }
+}
+
+impl From<NonZeroU32> for Error {
+    fn from(code: NonZeroU32) -> Self {
+        Error(code)
+    }
+}
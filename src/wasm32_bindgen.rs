@@ -14,14 +14,16 @@ use core::mem;
 use std::thread_local;
 
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 
-use crate::error::{BINDGEN_CRYPTO_UNDEF, BINDGEN_GRV_UNDEF};
+use crate::error::{BINDGEN_GRV_UNDEF, BINDGEN_NODE_CRYPTO_UNDEF, CUSTOM_RNG_FAILED};
 use crate::Error;
 
 #[derive(Clone, Debug)]
 enum RngSource {
     Node(NodeCrypto),
     Browser(BrowserCrypto),
+    Custom(js_sys::Function),
 }
 
 // JsValues are always per-thread, so we initialize RngSource for each thread.
@@ -54,33 +56,80 @@ pub fn getrandom_inner(dest: &mut [u8]) -> Result<(), Error> {
                     arr.copy_to(chunk);
                 }
             }
+            RngSource::Custom(f) => {
+                // Same chunking as the browser path: hand the callback a
+                // `Uint8Array` to fill, then copy the bytes into `dest`. Unlike
+                // `getRandomValues`, a user-provided source can throw, so we
+                // surface that as an error instead of copying out the
+                // zero-initialized buffer as if it were random.
+                for chunk in dest.chunks_mut(65536) {
+                    let arr = js_sys::Uint8Array::new_with_length(chunk.len() as u32);
+                    f.call1(&JsValue::null(), &arr)
+                        .map_err(|_| CUSTOM_RNG_FAILED)?;
+                    arr.copy_to(chunk);
+                }
+            }
         };
         Ok(())
     })
 }
 
-fn getrandom_init() -> Result<RngSource, Error> {
-    if let Ok(self_) = Global::get_self() {
-        // If `self` is defined then we're in a browser somehow (main window
-        // or web worker). We get `self.crypto` (called `msCrypto` on IE), so we
-        // can call `crypto.getRandomValues`. If `crypto` isn't defined, we
-        // assume we're in an older web browser and the OS RNG isn't available.
-
-        let crypto: BrowserCrypto = match (self_.crypto(), self_.ms_crypto()) {
-            (crypto, _) if !crypto.is_undefined() => crypto.into(),
-            (_, crypto) if !crypto.is_undefined() => crypto.into(),
-            _ => return Err(BINDGEN_CRYPTO_UNDEF),
-        };
+/// Register a custom JS entropy source for runtimes that provide neither
+/// `self.crypto.getRandomValues` nor Node's `crypto` module.
+///
+/// The supplied function is invoked with a `Uint8Array` (in chunks of at most
+/// 64 KiB) that it must fill with random bytes. Once registered it is preferred
+/// over the built-in browser/Node detection, letting integrators wire
+/// `getrandom` to host-provided entropy without forking the crate.
+pub fn set_custom_source(f: js_sys::Function) {
+    RNG_SOURCE.with(|s| {
+        *s.borrow_mut() = Some(RngSource::Custom(f));
+    });
+}
 
-        // Test if `crypto.getRandomValues` is undefined as well
-        if crypto.get_random_values_fn().is_undefined() {
-            return Err(BINDGEN_GRV_UNDEF);
+fn getrandom_init() -> Result<RngSource, Error> {
+    // We prefer `self` (defined in the main window or a web worker), but it is
+    // not available in every runtime. When it is missing (bundled ESM, Deno,
+    // edge workers, Node) we recover the true global object by evaluating
+    // `return this` in a fresh function, which yields the global regardless of
+    // the scope we happen to run in.
+    let global: Self_ = match Global::get_self() {
+        Ok(self_) => self_,
+        Err(_) => js_sys::Function::new_no_args("return this")
+            .call0(&JsValue::undefined())
+            .unwrap_or_else(|_| JsValue::undefined())
+            .unchecked_into(),
+    };
+
+    // Look for a Web Crypto object on the global (`msCrypto` on IE). If we find
+    // one we use `crypto.getRandomValues`; if not, we assume we're in Node and
+    // fall back to its `crypto` module.
+    let crypto = match (global.crypto(), global.ms_crypto()) {
+        (crypto, _) if !crypto.is_undefined() => crypto,
+        (_, crypto) if !crypto.is_undefined() => crypto,
+        // No Web Crypto object, so assume a Node-like runtime and try to load
+        // its `crypto` module. `module`/`require` may not exist (e.g. bundled
+        // ESM that only looks like Node), and `require("crypto")` can itself
+        // throw, so surface either as a recoverable error rather than letting
+        // the JS exception abort the program.
+        _ => {
+            if MODULE.is_undefined() {
+                return Err(BINDGEN_NODE_CRYPTO_UNDEF);
+            }
+            return match MODULE.require("crypto") {
+                Ok(n) => Ok(RngSource::Node(n)),
+                Err(_) => Err(BINDGEN_NODE_CRYPTO_UNDEF),
+            };
         }
+    };
+    let crypto: BrowserCrypto = crypto.into();
 
-        return Ok(RngSource::Browser(crypto));
+    // Test if `crypto.getRandomValues` is undefined as well
+    if crypto.get_random_values_fn().is_undefined() {
+        return Err(BINDGEN_GRV_UNDEF);
     }
 
-    return Ok(RngSource::Node(MODULE.require("crypto")));
+    Ok(RngSource::Browser(crypto))
 }
 
 #[wasm_bindgen]
@@ -117,6 +166,6 @@ extern "C" {
     #[wasm_bindgen(js_name = module)]
     static MODULE: NodeModule;
 
-    #[wasm_bindgen(method)]
-    fn require(this: &NodeModule, s: &str) -> NodeCrypto;
+    #[wasm_bindgen(method, catch)]
+    fn require(this: &NodeModule, s: &str) -> Result<NodeCrypto, JsValue>;
 }